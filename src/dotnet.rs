@@ -0,0 +1,24 @@
+use crate::{Log, LogParser, RawCloudWatchLog};
+use serde_json::Value;
+
+pub struct DotnetParser;
+
+impl LogParser for DotnetParser {
+    fn name(&self) -> &str {
+        "dotnet"
+    }
+
+    fn try_parse(&self, log: &RawCloudWatchLog) -> Result<Log, String> {
+        parse(log)
+    }
+}
+
+pub fn parse(log: &RawCloudWatchLog) -> Result<Log, String> {
+    let record = log.record.as_str().ok_or("record is not a string")?;
+    let value: Value =
+        serde_json::from_str(record).map_err(|error| format!("invalid JSON: {}", error))?;
+    if !value.is_object() {
+        return Err("JSON value is not an object".to_string());
+    }
+    Ok(Log::Formatted(value))
+}
@@ -0,0 +1,119 @@
+use crate::{Log, LogLevel};
+use chrono::{DateTime, FixedOffset};
+use regex::Regex;
+
+/// Composable post-processing filter over a parsed batch of [`Log`]s.
+///
+/// A [`Log`] passes the filter only when every configured predicate matches.
+/// Predicates that need a field which is missing from the record are treated as
+/// non-matching, so e.g. a `message_regex` filter drops records with no message.
+#[derive(Default)]
+pub struct LogFilter {
+    min_level: Option<LogLevel>,
+    start: Option<DateTime<FixedOffset>>,
+    end: Option<DateTime<FixedOffset>>,
+    guid: Option<String>,
+    message_regex: Option<Regex>,
+}
+
+impl LogFilter {
+    pub fn new() -> Self {
+        LogFilter::default()
+    }
+
+    pub fn min_level(mut self, level: LogLevel) -> Self {
+        self.min_level = Some(level);
+        self
+    }
+
+    pub fn time_range(mut self, start: DateTime<FixedOffset>, end: DateTime<FixedOffset>) -> Self {
+        self.start = Some(start);
+        self.end = Some(end);
+        self
+    }
+
+    pub fn guid(mut self, guid: &str) -> Self {
+        self.guid = Some(guid.to_string());
+        self
+    }
+
+    pub fn message_regex(mut self, regex: Regex) -> Self {
+        self.message_regex = Some(regex);
+        self
+    }
+
+    /// Keep only the logs that match every configured predicate.
+    pub fn apply(&self, logs: Vec<Log>) -> Vec<Log> {
+        logs.into_iter().filter(|log| self.matches(log)).collect()
+    }
+
+    pub fn matches(&self, log: &Log) -> bool {
+        if let Some(min) = &self.min_level {
+            match level_of(log) {
+                Some(level) if &level >= min => {}
+                _ => return false,
+            }
+        }
+        if self.start.is_some() || self.end.is_some() {
+            let timestamp = match timestamp_of(log).and_then(|t| DateTime::parse_from_rfc3339(&t).ok()) {
+                Some(ts) => ts,
+                None => return false,
+            };
+            if let Some(start) = self.start {
+                if timestamp < start {
+                    return false;
+                }
+            }
+            if let Some(end) = self.end {
+                if timestamp > end {
+                    return false;
+                }
+            }
+        }
+        if let Some(guid) = &self.guid {
+            match guid_of(log) {
+                Some(found) if &found == guid => {}
+                _ => return false,
+            }
+        }
+        if let Some(regex) = &self.message_regex {
+            match message_of(log) {
+                Some(message) if regex.is_match(&message) => {}
+                _ => return false,
+            }
+        }
+        true
+    }
+}
+
+fn level_of(log: &Log) -> Option<LogLevel> {
+    match log {
+        Log::Unformatted(structured) => structured.level.clone(),
+        Log::Formatted(value) => value.get("level").and_then(|v| v.as_str()).and_then(LogLevel::from_str_lenient),
+    }
+}
+
+fn timestamp_of(log: &Log) -> Option<String> {
+    match log {
+        Log::Unformatted(structured) => structured.timestamp.clone(),
+        Log::Formatted(value) => value.get("timestamp").and_then(|v| v.as_str()).map(|s| s.to_string()),
+    }
+}
+
+fn guid_of(log: &Log) -> Option<String> {
+    match log {
+        Log::Unformatted(structured) => structured.guid.clone(),
+        Log::Formatted(value) => value.get("guid").and_then(|v| v.as_str()).map(|s| s.to_string()),
+    }
+}
+
+fn message_of(log: &Log) -> Option<String> {
+    match log {
+        Log::Unformatted(structured) => match &structured.data {
+            serde_json::Value::String(s) => Some(s.clone()),
+            serde_json::Value::Null => None,
+            other => Some(other.to_string()),
+        },
+        Log::Formatted(value) => value.get("message").and_then(|v| v.as_str()).map(|s| s.to_string()),
+    }
+}
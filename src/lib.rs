@@ -1,10 +1,16 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::convert::TryFrom;
+use std::io::{BufRead, Read};
 use anyhow::{Error, Result};
 mod dotnet;
+mod filter;
 mod node;
 mod python;
+mod render;
+
+pub use filter::LogFilter;
+pub use render::{RenderOptions, Renderer};
 
 #[derive(Default, Debug, Deserialize, Clone)]
 pub struct RawCloudWatchLog {
@@ -19,17 +25,32 @@ pub struct StructuredLog {
     pub guid: Option<String>,
     pub level: Option<LogLevel>,
     pub data: Value,
+    pub log_group: Option<String>,
+    pub log_stream: Option<String>,
+}
+
+impl LogLevel {
+    /// Parse a level token leniently, returning `None` for unknown tokens so
+    /// runtime parsers can fall back gracefully instead of erroring. Matching is
+    /// case-insensitive and accepts the common runtime synonyms.
+    pub fn from_str_lenient(level: &str) -> Option<Self> {
+        match level.trim().to_uppercase().as_str() {
+            "TRACE" => Some(LogLevel::Trace),
+            "DEBUG" => Some(LogLevel::Debug),
+            "INFO" => Some(LogLevel::Info),
+            "WARN" | "WARNING" => Some(LogLevel::Warn),
+            "ERROR" | "ERR" => Some(LogLevel::Error),
+            "FATAL" | "CRITICAL" => Some(LogLevel::Fatal),
+            _ => None,
+        }
+    }
 }
 
 impl TryFrom<String> for LogLevel {
     type Error = anyhow::Error;
     fn try_from(level: String) -> Result<Self> {
-        match level.as_str() {
-            "INFO" => Ok(LogLevel::Info),
-            "WARN" => Ok(LogLevel::Warn),
-            "ERROR" => Ok(LogLevel::Error),
-            _ => Err(Error::msg(format!("Unable to parse {} as LogLevel", level))),
-        }
+        LogLevel::from_str_lenient(&level)
+            .ok_or_else(|| Error::msg(format!("Unable to parse {} as LogLevel", level)))
     }
 }
 
@@ -49,60 +70,272 @@ impl ToString for Log {
     }
 }
 
-#[derive(Debug, Serialize, PartialEq, Clone)]
+#[derive(Debug, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub enum LogLevel {
+    #[serde(rename(serialize = "TRACE"))]
+    Trace,
+    #[serde(rename(serialize = "DEBUG"))]
+    Debug,
     #[serde(rename(serialize = "INFO"))]
     Info,
     #[serde(rename(serialize = "WARN"))]
     Warn,
     #[serde(rename(serialize = "ERROR"))]
     Error,
+    #[serde(rename(serialize = "FATAL"))]
+    Fatal,
 }
 
-pub fn parse(logs: Vec<RawCloudWatchLog>) -> Vec<Log> {
-    logs.into_iter()
-        .filter(|log| match log.r#type.as_str() {
-            "function" => true,
-            _ => {
-                println!("{:?}", log);
-                false
-            }
-        })
-        .map(|log| match log.record {
-            Value::String(_) => try_parse_cloudwatch_log(&log),
-            _ => Err(Error::msg(format!("Expected String {}", log.record))),
+#[derive(Debug, Deserialize, Clone)]
+pub struct SubscriptionEventEnvelope {
+    pub awslogs: AwsLogsData,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct AwsLogsData {
+    pub data: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionEvent {
+    pub owner: String,
+    pub log_group: String,
+    pub log_stream: String,
+    pub subscription_filters: Vec<String>,
+    pub log_events: Vec<SubscriptionLogEvent>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SubscriptionLogEvent {
+    pub id: String,
+    pub timestamp: i64,
+    pub message: String,
+}
+
+pub trait LogParser {
+    /// Attempt to parse `log`. Returns `Err` with a short, human-readable
+    /// explanation of why this parser declined, so callers can report a
+    /// meaningful reason when every registered parser rejects a record.
+    fn try_parse(&self, log: &RawCloudWatchLog) -> Result<Log, String>;
+    fn name(&self) -> &str;
+}
+
+pub struct ParserRegistry {
+    parsers: Vec<Box<dyn LogParser>>,
+}
+
+impl ParserRegistry {
+    pub fn new() -> Self {
+        ParserRegistry {
+            parsers: Vec::new(),
+        }
+    }
+
+    pub fn with_defaults() -> Self {
+        let mut registry = ParserRegistry::new();
+        registry.register(Box::new(node::NodeParser));
+        registry.register(Box::new(python::PythonParser));
+        registry.register(Box::new(dotnet::DotnetParser));
+        registry
+    }
+
+    pub fn register(&mut self, parser: Box<dyn LogParser>) -> &mut Self {
+        self.parsers.push(parser);
+        self
+    }
+
+    pub fn register_first(&mut self, parser: Box<dyn LogParser>) -> &mut Self {
+        self.parsers.insert(0, parser);
+        self
+    }
+
+    pub fn parsers(&self) -> &[Box<dyn LogParser>] {
+        &self.parsers
+    }
+}
+
+impl Default for ParserRegistry {
+    fn default() -> Self {
+        ParserRegistry::with_defaults()
+    }
+}
+
+pub fn parse_subscription_event(input: &str, registry: &ParserRegistry) -> Result<Vec<Log>> {
+    Ok(parse_subscription_event_with_report(input, registry)?.parsed)
+}
+
+pub fn parse_subscription_event_with_report(
+    input: &str,
+    registry: &ParserRegistry,
+) -> Result<ParseReport> {
+    let envelope: SubscriptionEventEnvelope = serde_json::from_str(input)?;
+    let compressed = base64::decode(envelope.awslogs.data.trim())?;
+    let mut decoder = flate2::read::GzDecoder::new(&compressed[..]);
+    let mut decompressed = String::new();
+    decoder.read_to_string(&mut decompressed)?;
+    let event: SubscriptionEvent = serde_json::from_str(&decompressed)?;
+
+    let raw_logs = event
+        .log_events
+        .into_iter()
+        .map(|e| RawCloudWatchLog {
+            time: e.timestamp.to_string(),
+            r#type: "function".to_string(),
+            record: Value::String(e.message),
         })
-        .flatten()
-        .collect()
+        .collect();
+
+    let mut report = parse_with_report(raw_logs, registry);
+    for log in &mut report.parsed {
+        attach_group_metadata(log, &event.log_group, &event.log_stream);
+    }
+    Ok(report)
+}
+
+fn attach_group_metadata(log: &mut Log, log_group: &str, log_stream: &str) {
+    match log {
+        Log::Unformatted(structured) => {
+            structured.log_group = Some(log_group.to_string());
+            structured.log_stream = Some(log_stream.to_string());
+        }
+        Log::Formatted(value) => {
+            if let Value::Object(map) = value {
+                map.insert("logGroup".to_string(), Value::String(log_group.to_string()));
+                map.insert("logStream".to_string(), Value::String(log_stream.to_string()));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParseReport {
+    pub parsed: Vec<Log>,
+    pub failures: Vec<ParseFailure>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ParseFailure {
+    pub raw: Box<RawCloudWatchLog>,
+    pub reason: String,
+    pub attempted_parsers: Vec<String>,
+}
+
+pub fn parse(logs: Vec<RawCloudWatchLog>, registry: &ParserRegistry) -> Vec<Log> {
+    parse_with_report(logs, registry).parsed
 }
 
-fn try_parse_cloudwatch_log(log: &RawCloudWatchLog) -> Result<Log> {
-    match node::parse(log) {
-        Some(dto) => {
-            return Ok(dto);
+pub fn parse_with_report(logs: Vec<RawCloudWatchLog>, registry: &ParserRegistry) -> ParseReport {
+    let mut parsed = Vec::new();
+    let mut failures = Vec::new();
+    for log in logs {
+        match parse_one(log, registry) {
+            Ok(dto) => parsed.push(dto),
+            Err(failure) => failures.push(failure),
         }
-        _ => (),
-    };
-    match python::parse(log) {
-        Some(dto) => {
-            return Ok(dto);
+    }
+    ParseReport { parsed, failures }
+}
+
+fn parse_one(log: RawCloudWatchLog, registry: &ParserRegistry) -> Result<Log, ParseFailure> {
+    if log.r#type.as_str() != "function" {
+        return Err(ParseFailure {
+            reason: format!("unsupported record type `{}`", log.r#type),
+            attempted_parsers: Vec::new(),
+            raw: Box::new(log),
+        });
+    }
+    if !log.record.is_string() {
+        return Err(ParseFailure {
+            reason: format!("expected string record, found `{}`", log.record),
+            attempted_parsers: Vec::new(),
+            raw: Box::new(log),
+        });
+    }
+    match try_parse_cloudwatch_log(&log, registry) {
+        Ok(dto) => Ok(dto),
+        Err(error) => {
+            let attempted_parsers = registry
+                .parsers()
+                .iter()
+                .map(|parser| parser.name().to_string())
+                .collect();
+            Err(ParseFailure {
+                reason: error.to_string(),
+                attempted_parsers,
+                raw: Box::new(log),
+            })
+        }
+    }
+}
+
+/// Lazily parse an iterator of raw records, yielding one result at a time
+/// without buffering the whole batch. Each item is either a parsed [`Log`] or
+/// the [`ParseFailure`] describing why that record was rejected.
+pub fn parse_stream<'a, I>(
+    logs: I,
+    registry: &'a ParserRegistry,
+) -> impl Iterator<Item = Result<Log, ParseFailure>> + 'a
+where
+    I: Iterator<Item = RawCloudWatchLog> + 'a,
+{
+    logs.map(move |log| parse_one(log, registry))
+}
+
+/// Parse newline-delimited JSON [`RawCloudWatchLog`] objects from any reader,
+/// yielding parsed logs incrementally. Blank lines are skipped; malformed lines
+/// surface as a [`ParseFailure`] rather than aborting the stream.
+pub fn parse_reader<'a, R: BufRead + 'a>(
+    reader: R,
+    registry: &'a ParserRegistry,
+) -> impl Iterator<Item = Result<Log, ParseFailure>> + 'a {
+    reader.lines().filter_map(move |line| {
+        let line = match line {
+            Ok(line) => line,
+            Err(error) => {
+                return Some(Err(ParseFailure {
+                    reason: format!("failed to read line: {}", error),
+                    attempted_parsers: Vec::new(),
+                    raw: Box::new(RawCloudWatchLog::default()),
+                }))
+            }
+        };
+        if line.trim().is_empty() {
+            return None;
         }
-        _ => (),
-    };
-    match dotnet::parse(log) {
-        Some(dto) => {
-            return Ok(dto);
+        match serde_json::from_str::<RawCloudWatchLog>(&line) {
+            Ok(raw) => Some(parse_one(raw, registry)),
+            Err(error) => Some(Err(ParseFailure {
+                reason: format!("invalid JSON: {}", error),
+                attempted_parsers: Vec::new(),
+                raw: Box::new(RawCloudWatchLog {
+                    record: Value::String(line),
+                    ..Default::default()
+                }),
+            })),
         }
-        _ => (),
-    };
-    Err(Error::msg(format!("Unable to parse {:?}", log)))
+    })
+}
+
+fn try_parse_cloudwatch_log(log: &RawCloudWatchLog, registry: &ParserRegistry) -> Result<Log> {
+    let mut rejections = Vec::new();
+    for parser in registry.parsers() {
+        match parser.try_parse(log) {
+            Ok(dto) => return Ok(dto),
+            Err(reason) => rejections.push(format!("{}: {}", parser.name(), reason)),
+        }
+    }
+    if rejections.is_empty() {
+        return Err(Error::msg("no parsers are registered"));
+    }
+    Err(Error::msg(rejections.join("; ")))
 }
 
 #[cfg(test)]
 mod tests {
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     use super::try_parse_cloudwatch_log;
-    use crate::{LogLevel, RawCloudWatchLog, Log};
+    use crate::{Log, LogLevel, ParserRegistry, RawCloudWatchLog};
 
     #[test]
     fn can_parse_node() {
@@ -112,7 +345,7 @@ mod tests {
             serde_json::Value::String("2020-11-18T23:52:30.128Z\t6e48723a-1596-4313-a9af-e4da9214d637\tINFO\tHello World\n".to_string())
                 , ..Default::default()
             };
-        let output = try_parse_cloudwatch_log(&input);
+        let output = try_parse_cloudwatch_log(&input, &ParserRegistry::with_defaults());
 
         assert_eq!(output.is_ok(), true);
 
@@ -138,7 +371,7 @@ mod tests {
             ),
             ..Default::default()
         };
-        let output = try_parse_cloudwatch_log(&input);
+        let output = try_parse_cloudwatch_log(&input, &ParserRegistry::with_defaults());
 
         assert_eq!(output.is_ok(), true);
 
@@ -164,7 +397,7 @@ mod tests {
             time: "2020-11-18T23:52:30.128Z".to_string(),
             ..Default::default()
         };
-        let output = try_parse_cloudwatch_log(&input);
+        let output = try_parse_cloudwatch_log(&input, &ParserRegistry::with_defaults());
 
         assert_eq!(output.is_ok(), true);
 
@@ -179,10 +412,245 @@ mod tests {
         }
     }
 
+    #[test]
+    fn can_parse_subscription_event() {
+        use crate::parse_subscription_event;
+        use std::io::Write;
+
+        let payload = r#"{
+            "owner": "123456789012",
+            "logGroup": "/aws/lambda/my-function",
+            "logStream": "2020/11/18/[$LATEST]abcdef",
+            "subscriptionFilters": ["my-filter"],
+            "logEvents": [
+                {
+                    "id": "1",
+                    "timestamp": 1605743550128,
+                    "message": "2020-11-18T23:52:30.128Z\t6e48723a-1596-4313-a9af-e4da9214d637\tINFO\tHello World\n"
+                }
+            ]
+        }"#;
+
+        let mut encoder =
+            flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(payload.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let input = format!(r#"{{"awslogs":{{"data":"{}"}}}}"#, base64::encode(compressed));
+
+        let output = parse_subscription_event(&input, &ParserRegistry::with_defaults()).unwrap();
+        assert_eq!(output.len(), 1);
+        match &output[0] {
+            Log::Unformatted(log) => {
+                assert_eq!(log.level.clone().unwrap(), LogLevel::Info);
+                assert_eq!(log.log_group.clone().unwrap(), "/aws/lambda/my-function");
+                assert_eq!(log.log_stream.clone().unwrap(), "2020/11/18/[$LATEST]abcdef");
+            }
+            _ => panic!("Expected Cloudwatch formatted log"),
+        }
+    }
+
+    #[test]
+    fn report_quarantines_unparseable_logs() {
+        use crate::parse_with_report;
+
+        let logs = vec![
+            RawCloudWatchLog {
+                record: serde_json::Value::String(
+                    "2020-11-18T23:52:30.128Z\t6e48723a-1596-4313-a9af-e4da9214d637\tINFO\tHello World\n"
+                        .to_string(),
+                ),
+                r#type: "function".to_string(),
+                ..Default::default()
+            },
+            RawCloudWatchLog {
+                record: serde_json::Value::String("Bad log".to_string()),
+                r#type: "function".to_string(),
+                ..Default::default()
+            },
+        ];
+        let report = parse_with_report(logs, &ParserRegistry::with_defaults());
+        assert_eq!(report.parsed.len(), 1);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(
+            report.failures[0].attempted_parsers,
+            vec!["node", "python", "dotnet"]
+        );
+        let reason = &report.failures[0].reason;
+        assert!(reason.contains("node:"));
+        assert!(reason.contains("python:"));
+        assert!(reason.contains("dotnet:"));
+        assert!(!reason.contains("RawCloudWatchLog"));
+    }
+
+    struct AlwaysTagParser;
+
+    impl crate::LogParser for AlwaysTagParser {
+        fn name(&self) -> &str {
+            "always-tag"
+        }
+
+        fn try_parse(&self, _log: &RawCloudWatchLog) -> Result<Log, String> {
+            Ok(Log::Unformatted(crate::StructuredLog {
+                timestamp: None,
+                guid: None,
+                level: None,
+                data: serde_json::Value::String("tagged".to_string()),
+                log_group: None,
+                log_stream: None,
+            }))
+        }
+    }
+
+    #[test]
+    fn custom_parser_registered_first_takes_precedence() {
+        use crate::parse_with_report;
+
+        let log = RawCloudWatchLog {
+            record: serde_json::Value::String(
+                "2020-11-18T23:52:30.128Z\t6e48723a-1596-4313-a9af-e4da9214d637\tINFO\tHello World\n"
+                    .to_string(),
+            ),
+            r#type: "function".to_string(),
+            ..Default::default()
+        };
+
+        let mut registry = ParserRegistry::with_defaults();
+        registry.register_first(Box::new(AlwaysTagParser));
+
+        let report = parse_with_report(vec![log], &registry);
+        assert_eq!(report.failures.len(), 0);
+        match &report.parsed[0] {
+            Log::Unformatted(structured) => {
+                assert_eq!(structured.data, serde_json::Value::String("tagged".to_string()));
+            }
+            _ => panic!("expected custom parser to win"),
+        }
+    }
+
+    #[test]
+    fn custom_parser_registered_last_only_wins_when_defaults_decline() {
+        use crate::parse_with_report;
+
+        let log = RawCloudWatchLog {
+            record: serde_json::Value::String("not node, python, or dotnet".to_string()),
+            r#type: "function".to_string(),
+            ..Default::default()
+        };
+
+        let mut registry = ParserRegistry::with_defaults();
+        registry.register(Box::new(AlwaysTagParser));
+
+        let report = parse_with_report(vec![log], &registry);
+        assert_eq!(report.failures.len(), 0);
+        match &report.parsed[0] {
+            Log::Unformatted(structured) => {
+                assert_eq!(structured.data, serde_json::Value::String("tagged".to_string()));
+            }
+            _ => panic!("expected custom parser to win"),
+        }
+    }
+
+    #[test]
+    fn log_level_is_case_insensitive_with_synonyms() {
+        use std::convert::TryFrom;
+
+        assert_eq!(LogLevel::try_from("info".to_string()).unwrap(), LogLevel::Info);
+        assert_eq!(LogLevel::try_from("Warning".to_string()).unwrap(), LogLevel::Warn);
+        assert_eq!(LogLevel::try_from("err".to_string()).unwrap(), LogLevel::Error);
+        assert_eq!(LogLevel::try_from("CRITICAL".to_string()).unwrap(), LogLevel::Fatal);
+        assert!(LogLevel::from_str_lenient("nonsense").is_none());
+    }
+
+    #[test]
+    fn log_level_orders_by_severity() {
+        assert!(LogLevel::Trace < LogLevel::Debug);
+        assert!(LogLevel::Debug < LogLevel::Info);
+        assert!(LogLevel::Info < LogLevel::Warn);
+        assert!(LogLevel::Warn < LogLevel::Error);
+        assert!(LogLevel::Error < LogLevel::Fatal);
+    }
+
+    #[test]
+    fn filter_by_min_level_and_message() {
+        use crate::LogFilter;
+        use regex::Regex;
+
+        let raw = |record: &str| RawCloudWatchLog {
+            record: serde_json::Value::String(record.to_string()),
+            r#type: "function".to_string(),
+            ..Default::default()
+        };
+        let registry = ParserRegistry::with_defaults();
+        let info = try_parse_cloudwatch_log(
+            &raw("2020-11-18T23:52:30.128Z\t6e48723a-1596-4313-a9af-e4da9214d637\tINFO\tHello World\n"),
+            &registry,
+        )
+        .unwrap();
+        let error = try_parse_cloudwatch_log(
+            &raw("2020-11-18T23:52:31.000Z\t6e48723a-1596-4313-a9af-e4da9214d637\tERROR\tboom\n"),
+            &registry,
+        )
+        .unwrap();
+
+        let filtered = LogFilter::new()
+            .min_level(LogLevel::Warn)
+            .apply(vec![info.clone(), error.clone()]);
+        assert_eq!(filtered.len(), 1);
+
+        let filtered = LogFilter::new()
+            .message_regex(Regex::new("boom").unwrap())
+            .apply(vec![info, error]);
+        assert_eq!(filtered.len(), 1);
+    }
+
+    #[test]
+    fn render_unformatted_without_color() {
+        use crate::RenderOptions;
+
+        let registry = ParserRegistry::with_defaults();
+        let log = try_parse_cloudwatch_log(
+            &RawCloudWatchLog {
+                record: serde_json::Value::String(
+                    "2020-11-18T23:52:30.128Z\t6e48723a-1596-4313-a9af-e4da9214d637\tERROR\tboom\n"
+                        .to_string(),
+                ),
+                r#type: "function".to_string(),
+                ..Default::default()
+            },
+            &registry,
+        )
+        .unwrap();
+
+        let rendered = log.render(&RenderOptions {
+            no_color: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            rendered,
+            "2020-11-18T23:52:30.128Z ERROR 6e48723a-1596-4313-a9af-e4da9214d637 boom"
+        );
+    }
+
+    #[test]
+    fn parse_reader_yields_logs_incrementally() {
+        use crate::parse_reader;
+
+        let input = concat!(
+            "{\"time\":\"\",\"type\":\"function\",\"record\":\"2020-11-18T23:52:30.128Z\\t6e48723a-1596-4313-a9af-e4da9214d637\\tINFO\\tHello World\\n\"}\n",
+            "\n",
+            "not json\n"
+        );
+        let registry = ParserRegistry::with_defaults();
+        let results: Vec<_> = parse_reader(input.as_bytes(), &registry).collect();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+    }
+
     #[test]
     fn cannot_parse() {
         let input = RawCloudWatchLog { record: serde_json::Value::String("Bad log".to_string()), ..Default::default()};
-        let output = try_parse_cloudwatch_log(&input);
+        let output = try_parse_cloudwatch_log(&input, &ParserRegistry::with_defaults());
         assert_eq!(output.is_err(), true);
     }
 }
@@ -0,0 +1,36 @@
+use crate::{Log, LogLevel, LogParser, RawCloudWatchLog, StructuredLog};
+use serde_json::Value;
+use std::convert::TryFrom;
+
+pub struct NodeParser;
+
+impl LogParser for NodeParser {
+    fn name(&self) -> &str {
+        "node"
+    }
+
+    fn try_parse(&self, log: &RawCloudWatchLog) -> Result<Log, String> {
+        parse(log)
+    }
+}
+
+pub fn parse(log: &RawCloudWatchLog) -> Result<Log, String> {
+    let record = log.record.as_str().ok_or("record is not a string")?;
+    let parts: Vec<&str> = record.splitn(4, '\t').collect();
+    if parts.len() != 4 {
+        return Err(format!(
+            "expected 4 tab-separated fields, found {}",
+            parts.len()
+        ));
+    }
+    let level = LogLevel::try_from(parts[2].to_string())
+        .map_err(|_| format!("unrecognized log level `{}`", parts[2]))?;
+    Ok(Log::Unformatted(StructuredLog {
+        timestamp: Some(parts[0].to_string()),
+        guid: Some(parts[1].to_string()),
+        level: Some(level),
+        data: Value::String(parts[3].to_string()),
+        log_group: None,
+        log_stream: None,
+    }))
+}
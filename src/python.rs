@@ -0,0 +1,49 @@
+use crate::{Log, LogLevel, LogParser, RawCloudWatchLog, StructuredLog};
+use serde_json::Value;
+use std::convert::TryFrom;
+
+pub struct PythonParser;
+
+impl LogParser for PythonParser {
+    fn name(&self) -> &str {
+        "python"
+    }
+
+    fn try_parse(&self, log: &RawCloudWatchLog) -> Result<Log, String> {
+        parse(log)
+    }
+}
+
+pub fn parse(log: &RawCloudWatchLog) -> Result<Log, String> {
+    let record = log.record.as_str().ok_or("record is not a string")?;
+    if !record.starts_with('[') {
+        return Err("record does not start with a `[LEVEL]` tag".to_string());
+    }
+    let parts: Vec<&str> = record.splitn(3, '\t').collect();
+    if parts.len() != 3 {
+        return Err(format!(
+            "expected 3 tab-separated fields, found {}",
+            parts.len()
+        ));
+    }
+    let level_token = parts[0].trim_matches(|c| c == '[' || c == ']').to_string();
+    let level = LogLevel::try_from(level_token.clone())
+        .map_err(|_| format!("unrecognized log level `{}`", level_token))?;
+    let mut meta = parts[1].split_whitespace();
+    let timestamp = meta
+        .next()
+        .ok_or("missing timestamp in metadata field")?
+        .to_string();
+    let guid = meta
+        .next()
+        .ok_or("missing guid in metadata field")?
+        .to_string();
+    Ok(Log::Unformatted(StructuredLog {
+        timestamp: Some(timestamp),
+        guid: Some(guid),
+        level: Some(level),
+        data: Value::String(parts[2].to_string()),
+        log_group: None,
+        log_stream: None,
+    }))
+}
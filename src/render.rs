@@ -0,0 +1,133 @@
+use crate::{Log, LogLevel};
+use std::io::IsTerminal;
+
+/// Options controlling how a [`Log`] is rendered for terminal display.
+pub struct RenderOptions {
+    /// Template for unformatted records. Supported placeholders:
+    /// `{timestamp}`, `{level}`, `{guid}`, `{message}`.
+    pub template: String,
+    /// Disable ANSI colors explicitly. Colors are also disabled automatically
+    /// when stdout is not a TTY, regardless of this flag.
+    pub no_color: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            template: "{timestamp} {level} {guid} {message}".to_string(),
+            no_color: false,
+        }
+    }
+}
+
+impl RenderOptions {
+    fn color_enabled(&self) -> bool {
+        !self.no_color && std::io::stdout().is_terminal()
+    }
+}
+
+/// Convenience wrapper binding a set of [`RenderOptions`] to reuse across logs.
+pub struct Renderer {
+    options: RenderOptions,
+}
+
+impl Renderer {
+    pub fn new(options: RenderOptions) -> Self {
+        Renderer { options }
+    }
+
+    pub fn render(&self, log: &Log) -> String {
+        log.render(&self.options)
+    }
+}
+
+impl Log {
+    /// Render the log for terminal display, applying the template and
+    /// severity-based coloring described by `options`.
+    pub fn render(&self, options: &RenderOptions) -> String {
+        let color = options.color_enabled();
+        match self {
+            Log::Unformatted(structured) => {
+                let level = structured.level.clone();
+                let message = message_text(&structured.data);
+                let line = fill_template(
+                    &options.template,
+                    &[
+                        ("timestamp", structured.timestamp.as_deref().unwrap_or("")),
+                        ("level", level.as_ref().map(level_label).unwrap_or("")),
+                        ("guid", structured.guid.as_deref().unwrap_or("")),
+                        ("message", message.trim_end()),
+                    ],
+                );
+                colorize(&line, level.as_ref(), color)
+            }
+            Log::Formatted(value) => {
+                let pretty =
+                    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string());
+                let level = value
+                    .get("level")
+                    .and_then(|v| v.as_str())
+                    .and_then(LogLevel::from_str_lenient);
+                colorize(&pretty, level.as_ref(), color)
+            }
+        }
+    }
+}
+
+/// Fill `template` with `{name}` placeholders in a single left-to-right pass,
+/// so substituted values (which may themselves contain `{...}` text from
+/// untrusted log content) are never re-scanned for further placeholders.
+fn fill_template(template: &str, values: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}').map(|i| open + i) else {
+            out.push_str(rest);
+            return out;
+        };
+        let name = &rest[open + 1..close];
+        match values.iter().find(|(n, _)| *n == name) {
+            Some((_, value)) => {
+                out.push_str(&rest[..open]);
+                out.push_str(value);
+            }
+            None => out.push_str(&rest[..=close]),
+        }
+        rest = &rest[close + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn level_label(level: &LogLevel) -> &'static str {
+    match level {
+        LogLevel::Trace => "TRACE",
+        LogLevel::Debug => "DEBUG",
+        LogLevel::Info => "INFO",
+        LogLevel::Warn => "WARN",
+        LogLevel::Error => "ERROR",
+        LogLevel::Fatal => "FATAL",
+    }
+}
+
+fn message_text(data: &serde_json::Value) -> String {
+    match data {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn colorize(text: &str, level: Option<&LogLevel>, color: bool) -> String {
+    if !color {
+        return text.to_string();
+    }
+    let code = match level {
+        Some(LogLevel::Error) | Some(LogLevel::Fatal) => "\x1b[31m",
+        Some(LogLevel::Warn) => "\x1b[33m",
+        Some(LogLevel::Info) => "\x1b[32m",
+        Some(LogLevel::Debug) | Some(LogLevel::Trace) => "\x1b[2m",
+        None => return text.to_string(),
+    };
+    format!("{}{}\x1b[0m", code, text)
+}